@@ -0,0 +1,132 @@
+//! Optional `finch.toml`-driven extension classification, letting a project mark its own
+//! text formats as `string`/`bytes` and annotate per-extension alignment, instead of being
+//! limited to [`AssetOutputType::guess_from_filepath`]'s built-in whitelist.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{AssetOutputType, FinchError};
+
+/// A parsed `finch.toml`: one section per file extension (without the leading `.`), e.g.
+///
+/// ```toml
+/// [shader]
+/// type = "string"
+///
+/// [bin]
+/// type = "bytes"
+/// align = 16
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    #[serde(flatten)]
+    extensions: HashMap<String, ExtensionConfig>,
+    /// Canonicalized paths that must not be re-embedded as assets: the default
+    /// `{directory}/finch.toml` and, if different, whichever file was actually loaded.
+    #[serde(skip)]
+    excluded_paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ExtensionConfig {
+    #[serde(rename = "type")]
+    output_type: Option<ConfigOutputType>,
+    align: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ConfigOutputType {
+    String,
+    Bytes,
+}
+
+impl From<ConfigOutputType> for AssetOutputType {
+    fn from(output_type: ConfigOutputType) -> Self {
+        match output_type {
+            ConfigOutputType::String => AssetOutputType::String,
+            ConfigOutputType::Bytes => AssetOutputType::Bytes,
+        }
+    }
+}
+
+impl Config {
+    /// Loads `finch.toml` from `explicit_path`, or from `directory/finch.toml` if present,
+    /// falling back to [`Config::default`] (the built-in extension whitelist only) when
+    /// neither exists.
+    pub fn load(directory: &Path, explicit_path: Option<&Path>) -> Result<Self, FinchError> {
+        let default_path = directory.join("finch.toml");
+        let path = match explicit_path {
+            Some(path) => path.to_path_buf(),
+            None => default_path.clone(),
+        };
+
+        // The default location is reserved for config regardless of which file actually got
+        // loaded, so it's never picked up as an asset.
+        let mut excluded_paths: Vec<PathBuf> = default_path.canonicalize().into_iter().collect();
+
+        if !path.exists() {
+            if let Some(path) = explicit_path {
+                return Err(FinchError::InvalidConfig(format!(
+                    "config file not found: {}",
+                    path.display()
+                )));
+            }
+            return Ok(Self {
+                excluded_paths,
+                ..Self::default()
+            });
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+
+        let mut config: Config = toml::from_str(&contents)
+            .map_err(|err| FinchError::InvalidConfig(format!("{}: {}", path.display(), err)))?;
+
+        if let Ok(canonical) = path.canonicalize() {
+            excluded_paths.push(canonical);
+        }
+        config.excluded_paths = excluded_paths;
+
+        Ok(config)
+    }
+
+    /// Whether `path` is the config file itself, and so must be skipped during asset
+    /// traversal rather than embedded.
+    pub(crate) fn is_excluded(&self, path: &Path) -> bool {
+        path.canonicalize()
+            .is_ok_and(|canonical| self.excluded_paths.contains(&canonical))
+    }
+
+    fn extension_config(&self, path: &Path) -> Option<&ExtensionConfig> {
+        let extension = path.extension()?.to_str()?;
+        self.extensions.get(extension)
+    }
+
+    /// Classifies `path` as `string`/`bytes`, preferring an explicit `finch.toml` entry and
+    /// falling back to [`AssetOutputType::guess_from_filepath`].
+    pub(crate) fn classify(&self, path: &Path) -> AssetOutputType {
+        self.extension_config(path)
+            .and_then(|config| config.output_type)
+            .map(AssetOutputType::from)
+            .unwrap_or_else(|| AssetOutputType::guess_from_filepath(path))
+    }
+
+    /// Returns the configured alignment (in bytes) for `path`'s extension, if any.
+    pub fn align(&self, path: &Path) -> Option<u32> {
+        self.extension_config(path).and_then(|config| config.align)
+    }
+
+    /// Returns the ` __attribute__((aligned(N)))` suffix for `path`'s extension, or an empty
+    /// string if no alignment is configured.
+    pub fn align_attribute(&self, path: &Path) -> String {
+        match self.align(path) {
+            Some(align) => format!(" __attribute__((aligned({})))", align),
+            None => String::new(),
+        }
+    }
+}
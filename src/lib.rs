@@ -0,0 +1,1102 @@
+//! Core asset-embedding logic for `finch`, usable both from the `finch` CLI and from a
+//! `build.rs` via [`generate_files`] / [`generate`].
+
+use std::{
+    fmt,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::DeflateEncoder, Compression};
+
+mod config;
+mod minify;
+
+pub use config::Config;
+
+/// Errors that can occur while generating a header/impl pair from an asset directory.
+#[derive(Debug)]
+pub enum FinchError {
+    /// `directory` does not exist or could not be canonicalized.
+    InvalidDirectory(PathBuf),
+    /// `directory` exists but is not a directory.
+    NotADirectory(PathBuf),
+    /// A `finch.toml` was missing (when explicitly requested) or failed to parse.
+    InvalidConfig(String),
+    /// An incompatible combination of [`GenerateOptions`] was requested.
+    UnsupportedOptions(String),
+    /// An I/O error occurred while reading an asset or writing generated output.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for FinchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FinchError::InvalidDirectory(path) => {
+                write!(f, "invalid directory path: {}", path.display())
+            }
+            FinchError::NotADirectory(path) => {
+                write!(f, "path is not a directory: {}", path.display())
+            }
+            FinchError::InvalidConfig(message) => write!(f, "invalid config: {}", message),
+            FinchError::UnsupportedOptions(message) => write!(f, "unsupported options: {}", message),
+            FinchError::Io(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for FinchError {}
+
+impl From<std::io::Error> for FinchError {
+    fn from(err: std::io::Error) -> Self {
+        FinchError::Io(err)
+    }
+}
+
+/// Options controlling how an asset directory is turned into a C header/impl pair.
+///
+/// Build with [`GenerateOptions::new`] and the builder methods, then pass to
+/// [`generate`] or [`generate_files`].
+#[derive(Debug, Clone)]
+pub struct GenerateOptions {
+    directory: PathBuf,
+    output_name: Option<String>,
+    output_dir: Option<PathBuf>,
+    c_file: bool,
+    compress: bool,
+    lookup: bool,
+    minify: bool,
+    config_path: Option<PathBuf>,
+}
+
+impl GenerateOptions {
+    /// Starts a builder for embedding every asset under `directory`.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            output_name: None,
+            output_dir: None,
+            c_file: false,
+            compress: false,
+            lookup: false,
+            minify: false,
+            config_path: None,
+        }
+    }
+
+    /// Sets the base name of the generated header/impl (and the embedded struct name).
+    /// Defaults to the directory's file stem.
+    pub fn output_name(mut self, output_name: impl Into<String>) -> Self {
+        self.output_name = Some(output_name.into());
+        self
+    }
+
+    /// Writes the generated header/impl pair into `output_dir` instead of the current
+    /// directory. [`generate_files`] creates it if it doesn't already exist.
+    pub fn output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    /// Emits a separate `.c` implementation file instead of a single-file header with a
+    /// guarded `{NAME}_IMPLEMENTATION` section.
+    pub fn c_file(mut self, c_file: bool) -> Self {
+        self.c_file = c_file;
+        self
+    }
+
+    /// Stores assets DEFLATE-compressed and generates an inflate accessor for each one.
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Generates a `{output_name}_get(path, out_len)` runtime lookup, backed by a
+    /// compile-time minimal perfect hash over every asset's relative path.
+    pub fn lookup(mut self, lookup: bool) -> Self {
+        self.lookup = lookup;
+        self
+    }
+
+    /// Minifies `String` assets (stripping insignificant whitespace/comments for
+    /// recognized extensions) before embedding.
+    pub fn minify(mut self, minify: bool) -> Self {
+        self.minify = minify;
+        self
+    }
+
+    /// Reads extension classification and alignment from `config_path` instead of the
+    /// default `{directory}/finch.toml`.
+    pub fn config(mut self, config_path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(config_path.into());
+        self
+    }
+
+    fn resolve(&self) -> Result<(PathBuf, String, Config), FinchError> {
+        if self.compress && self.lookup {
+            return Err(FinchError::UnsupportedOptions(
+                "--compress and --lookup cannot be combined: the lookup table would hand back \
+                 raw DEFLATE bytes with no way to reach the inflating `{name}_data()` accessor"
+                    .to_string(),
+            ));
+        }
+
+        let directory = self
+            .directory
+            .canonicalize()
+            .map_err(|_| FinchError::InvalidDirectory(self.directory.clone()))?;
+
+        if !directory.is_dir() {
+            return Err(FinchError::NotADirectory(directory));
+        }
+
+        let output_name = match &self.output_name {
+            Some(name) => name.clone(),
+            None => directory.file_stem().unwrap().to_str().unwrap().to_string(),
+        };
+
+        let config = Config::load(&directory, self.config_path.as_deref())?;
+
+        Ok((directory, output_name, config))
+    }
+
+    fn flags(&self) -> GenFlags {
+        GenFlags {
+            compress: self.compress,
+            lookup: self.lookup,
+            minify: self.minify,
+        }
+    }
+}
+
+/// The generation toggles that get threaded through the recursive codegen functions,
+/// bundled together so those functions don't balloon into a long parameter list.
+#[derive(Debug, Clone, Copy)]
+struct GenFlags {
+    compress: bool,
+    lookup: bool,
+    minify: bool,
+}
+
+/// Generates the header and implementation as a single combined stream (header followed by
+/// a `{NAME}_IMPLEMENTATION`-guarded impl section), writing to `output`.
+///
+/// Returns the list of asset files that were read, so a `build.rs` can emit
+/// `cargo:rerun-if-changed=` lines for them.
+pub fn generate(opts: &GenerateOptions, output: &mut impl Write) -> Result<Vec<PathBuf>, FinchError> {
+    let (directory, output_name, config) = opts.resolve()?;
+    let flags = opts.flags();
+
+    let mut files = Vec::new();
+    generate_header(&directory, &output_name, output, &flags, &config, &mut files)?;
+    generate_impl(&directory, &output_name, output, true, &flags, &config)?;
+
+    Ok(files)
+}
+
+/// Generates `{output_name}.h` (and, if [`GenerateOptions::c_file`] is set, a separate
+/// `{output_name}.c`) on disk in [`GenerateOptions::output_dir`] (the current directory by
+/// default), creating that directory if it doesn't already exist.
+///
+/// Returns the list of asset files that were read, so a `build.rs` can emit
+/// `cargo:rerun-if-changed=` lines for them.
+pub fn generate_files(opts: &GenerateOptions) -> Result<Vec<PathBuf>, FinchError> {
+    let (directory, output_name, config) = opts.resolve()?;
+    let flags = opts.flags();
+
+    let output_dir = opts.output_dir.as_deref().unwrap_or_else(|| Path::new("."));
+    std::fs::create_dir_all(output_dir)?;
+
+    let mut files = Vec::new();
+
+    let header_path = output_dir.join(format!("{}.h", output_name));
+    let mut header_file = File::create(&header_path)?;
+    generate_header(&directory, &output_name, &mut header_file, &flags, &config, &mut files)?;
+
+    if opts.c_file {
+        let impl_path = output_dir.join(format!("{}.c", output_name));
+        let mut impl_file = File::create(impl_path)?;
+        generate_impl(&directory, &output_name, &mut impl_file, false, &flags, &config)?;
+    } else {
+        let mut header_file = std::fs::OpenOptions::new().append(true).open(&header_path)?;
+        generate_impl(&directory, &output_name, &mut header_file, true, &flags, &config)?;
+    }
+
+    Ok(files)
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum AssetOutputType {
+    String,
+    Bytes,
+}
+
+impl AssetOutputType {
+    pub fn guess_from_filepath(path: &Path) -> Self {
+        let extension = path.extension();
+
+        if let Some(extension) = extension {
+            if let Some(extension) = extension.to_str() {
+                match extension {
+                    "txt" | "json" | "xml" | "csv" | "html" | "htm" | "css" | "js" | "md"
+                    | "toml" | "rs" | "glsl" | "frag" | "vert" => Self::String,
+                    _ => Self::Bytes,
+                }
+            } else {
+                Self::Bytes
+            }
+        } else {
+            Self::Bytes
+        }
+    }
+}
+
+/// DEFLATEs `data` at the default compression level, for embedding as the on-disk
+/// representation of a compressed asset.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn generate_header(
+    directory: &Path,
+    output_name: &str,
+    output: &mut impl Write,
+    flags: &GenFlags,
+    config: &Config,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), FinchError> {
+    writeln!(output, "#ifndef {}_H", output_name.to_uppercase())?;
+    writeln!(output, "#define {}_H", output_name.to_uppercase())?;
+
+    writeln!(output, "#include <stdint.h>")?;
+    writeln!(output, "#include <stddef.h>")?;
+
+    writeln!(output, "#ifdef __cplusplus")?;
+    writeln!(output, "extern \"C\" {{")?;
+    writeln!(output, "#endif")?;
+
+    if flags.compress {
+        writeln!(output, "int finch_inflate(uint8_t *dest, size_t dest_len, const uint8_t *src, size_t src_len);")?;
+    }
+
+    if flags.lookup {
+        writeln!(output, "typedef struct {{")?;
+        writeln!(output, "const char* path;")?;
+        writeln!(output, "const uint8_t* data;")?;
+        writeln!(output, "size_t len;")?;
+        writeln!(output, "}} __{}_entry_t;", output_name)?;
+        writeln!(
+            output,
+            "const uint8_t* {}_get(const char* path, size_t* out_len);",
+            output_name
+        )?;
+    }
+
+    writeln!(output, "typedef struct {{")?;
+
+    for entry in directory.read_dir()? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if config.is_excluded(&path) {
+            continue;
+        }
+
+        struct_fieldify(&path, output, flags, config, files)?;
+    }
+
+    writeln!(output, "}} __{}_t;", output_name)?;
+
+    writeln!(output, "extern const __{}_t {};", output_name, output_name)?;
+
+    if flags.compress {
+        for entry in directory.read_dir()? {
+            let entry = entry?;
+            let path = entry.path();
+
+            if config.is_excluded(&path) {
+                continue;
+            }
+
+            struct_fieldify_accessors(&path, output, "")?;
+        }
+    }
+
+    writeln!(output, "#ifdef __cplusplus")?;
+    writeln!(output, "}}")?;
+    writeln!(output, "#endif")?;
+
+    writeln!(output, "#endif")?;
+
+    Ok(())
+}
+
+fn struct_fieldify(
+    path: &Path,
+    output: &mut impl Write,
+    flags: &GenFlags,
+    config: &Config,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), FinchError> {
+    let name = path.file_stem().unwrap().to_str().unwrap();
+    let name = name.replace('-', "_");
+
+    if path.is_dir() {
+        writeln!(output, "struct {{")?;
+
+        for entry in path.read_dir()? {
+            let entry = entry?;
+            let path = entry.path();
+
+            struct_fieldify(&path, output, flags, config, files)?;
+        }
+
+        writeln!(output, "}} {};", name)?;
+    } else {
+        files.push(path.to_path_buf());
+
+        let align = config.align_attribute(path);
+
+        if flags.compress {
+            let contents = std::fs::read(path)?;
+            let compressed_len = deflate(&contents).len();
+
+            writeln!(output, "const uint8_t {}[{}]{};", name, compressed_len, align)?;
+            writeln!(output, "const size_t {}_len;", name)?;
+            writeln!(output, "const size_t {}_compressed_len;", name)?;
+
+            return Ok(());
+        }
+
+        let output_type = config.classify(path);
+
+        match output_type {
+            AssetOutputType::String => {
+                let filesize = string_asset_len(path, flags.minify)?;
+                writeln!(output, "const char {}[{} + 1]{};", name, filesize, align)?;
+                writeln!(output, "const size_t {}_len;", name)?;
+            }
+            AssetOutputType::Bytes => {
+                let filesize = path.metadata()?.len();
+                writeln!(output, "const uint8_t {}[{}]{};", name, filesize, align)?;
+                writeln!(output, "const size_t {}_len;", name)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The byte length a `String` asset will have once embedded: the minified length if
+/// `minify` is set and `path`'s extension has a minifier, otherwise the file's own size.
+/// Kept in sync with the content [`struct_fieldify_impl`] actually writes, since the header
+/// (declaring this size) is generated before the impl.
+fn string_asset_len(path: &Path, minify: bool) -> Result<u64, FinchError> {
+    if minify {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(crate::minify::minify(path, &contents).len() as u64)
+    } else {
+        Ok(path.metadata()?.len())
+    }
+}
+
+/// Declares the lazy `{name}_data()` accessor for every compressed leaf asset under `path`.
+/// `field_prefix` is the dotted path of struct fields leading here (e.g. `"shaders."`),
+/// also used (with dots replaced by underscores) to keep generated identifiers globally
+/// unique across nested directories.
+fn struct_fieldify_accessors(
+    path: &Path,
+    output: &mut impl Write,
+    field_prefix: &str,
+) -> Result<(), FinchError> {
+    let name = path.file_stem().unwrap().to_str().unwrap();
+    let name = name.replace('-', "_");
+
+    if path.is_dir() {
+        let prefix = format!("{}{}.", field_prefix, name);
+
+        for entry in path.read_dir()? {
+            let entry = entry?;
+            let path = entry.path();
+
+            struct_fieldify_accessors(&path, output, &prefix)?;
+        }
+    } else {
+        let qualified = format!("{}{}", field_prefix, name).replace('.', "_");
+
+        writeln!(output, "const uint8_t* {}_data(void);", qualified)?;
+    }
+
+    Ok(())
+}
+
+fn generate_impl(
+    directory: &Path,
+    output_name: &str,
+    output: &mut impl Write,
+    single_file: bool,
+    flags: &GenFlags,
+    config: &Config,
+) -> Result<(), FinchError> {
+    if single_file {
+        writeln!(output, "#ifdef {}_IMPLEMENTATION", output_name.to_uppercase())?;
+    } else {
+        writeln!(output, "#include \"{}.h\"", output_name)?;
+    }
+
+    writeln!(output, "#include <stddef.h>")?;
+    writeln!(output, "#include <stdint.h>")?;
+
+    if flags.lookup {
+        writeln!(output, "#include <string.h>")?;
+    }
+
+    writeln!(output, "#ifdef __cplusplus")?;
+    writeln!(output, "extern \"C\" {{")?;
+    writeln!(output, "#endif")?;
+
+    if flags.compress {
+        writeln!(output, "{}", INFLATE_DECODER)?;
+    }
+
+    if flags.lookup {
+        writeln!(output, "{}", FNV1A_HELPER)?;
+    }
+
+    writeln!(output, "const __{}_t {} = {{", output_name, output_name)?;
+
+    for entry in directory.read_dir()? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if config.is_excluded(&path) {
+            continue;
+        }
+
+        struct_fieldify_impl(&path, output, flags, config)?;
+    }
+
+    writeln!(output, "}};")?;
+
+    if flags.compress {
+        for entry in directory.read_dir()? {
+            let entry = entry?;
+            let path = entry.path();
+
+            if config.is_excluded(&path) {
+                continue;
+            }
+
+            struct_fieldify_impl_accessors(&path, output, output_name, "", config)?;
+        }
+    }
+
+    if flags.lookup {
+        let mut entries = Vec::new();
+        collect_lookup_entries(directory, "", "", config, &mut entries)?;
+        emit_lookup_table(output, output_name, flags.compress, &entries)?;
+    }
+
+    writeln!(output, "#ifdef __cplusplus")?;
+    writeln!(output, "}}")?;
+    writeln!(output, "#endif")?;
+
+    if single_file {
+        writeln!(output, "#undef {}_IMPLEMENTATION", output_name.to_uppercase())?;
+        writeln!(output, "#endif")?;
+    }
+
+    Ok(())
+}
+
+/// A leaf asset's relative path (the runtime lookup key) paired with the dotted field path
+/// used to reach its data/length inside the generated struct.
+struct LookupEntry {
+    key: String,
+    field_path: String,
+}
+
+fn collect_lookup_entries(
+    directory: &Path,
+    key_prefix: &str,
+    field_prefix: &str,
+    config: &Config,
+    entries: &mut Vec<LookupEntry>,
+) -> Result<(), FinchError> {
+    for entry in directory.read_dir()? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if config.is_excluded(&path) {
+            continue;
+        }
+
+        let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+        let field_name = path.file_stem().unwrap().to_str().unwrap().replace('-', "_");
+
+        if path.is_dir() {
+            collect_lookup_entries(
+                &path,
+                &format!("{}{}/", key_prefix, file_name),
+                &format!("{}{}.", field_prefix, field_name),
+                config,
+                entries,
+            )?;
+        } else {
+            entries.push(LookupEntry {
+                key: format!("{}{}", key_prefix, file_name),
+                field_path: format!("{}{}", field_prefix, field_name),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// FNV-1a seeded with `seed`, matching `finch_fnv1a` in [`FNV1A_HELPER`] bit-for-bit.
+///
+/// FNV-1a's low bits mix poorly on their own, which matters a lot once the result is
+/// reduced mod a small bucket/slot count, so the accumulated hash is run through a
+/// murmur-style finalizer before being returned.
+fn fnv1a(seed: u32, data: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5 ^ seed;
+
+    for &byte in data {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+
+    hash
+}
+
+/// A compile-time minimal perfect hash (CHD-style: bucket by a first hash, then find a
+/// per-bucket displacement that slots every key into a distinct, empty final slot).
+struct Mph {
+    buckets: u32,
+    slots: u32,
+    displacements: Vec<u32>,
+    slot_to_key: Vec<Option<usize>>,
+}
+
+fn build_mph(keys: &[String]) -> Mph {
+    let n = keys.len();
+    let slots = n.max(1) as u32;
+    let buckets = n.div_ceil(4).max(1) as u32;
+
+    let mut bucket_keys: Vec<Vec<usize>> = vec![Vec::new(); buckets as usize];
+    for (i, key) in keys.iter().enumerate() {
+        let bucket = fnv1a(0, key.as_bytes()) % buckets;
+        bucket_keys[bucket as usize].push(i);
+    }
+
+    let mut bucket_order: Vec<u32> = (0..buckets).collect();
+    bucket_order.sort_by_key(|&b| std::cmp::Reverse(bucket_keys[b as usize].len()));
+
+    let mut slot_occupied = vec![false; slots as usize];
+    let mut slot_to_key: Vec<Option<usize>> = vec![None; slots as usize];
+    let mut displacements = vec![0u32; buckets as usize];
+
+    for bucket in bucket_order {
+        let key_indices = &bucket_keys[bucket as usize];
+        if key_indices.is_empty() {
+            continue;
+        }
+
+        let mut displacement = 0u32;
+        loop {
+            let mut candidate_slots = Vec::with_capacity(key_indices.len());
+            let mut collided = false;
+
+            for &key_index in key_indices {
+                let slot = fnv1a(displacement, keys[key_index].as_bytes()) % slots;
+                if slot_occupied[slot as usize] || candidate_slots.contains(&slot) {
+                    collided = true;
+                    break;
+                }
+                candidate_slots.push(slot);
+            }
+
+            if !collided {
+                for (&key_index, &slot) in key_indices.iter().zip(candidate_slots.iter()) {
+                    slot_occupied[slot as usize] = true;
+                    slot_to_key[slot as usize] = Some(key_index);
+                }
+                displacements[bucket as usize] = displacement;
+                break;
+            }
+
+            displacement += 1;
+        }
+    }
+
+    Mph {
+        buckets,
+        slots,
+        displacements,
+        slot_to_key,
+    }
+}
+
+fn emit_lookup_table(
+    output: &mut impl Write,
+    output_name: &str,
+    compress: bool,
+    entries: &[LookupEntry],
+) -> Result<(), FinchError> {
+    let keys: Vec<String> = entries.iter().map(|e| e.key.clone()).collect();
+    let mph = build_mph(&keys);
+
+    write!(output, "static const uint32_t {}_mph_displacements[{}] = {{", output_name, mph.buckets)?;
+    for d in &mph.displacements {
+        write!(output, "{}u, ", d)?;
+    }
+    writeln!(output, "}};")?;
+
+    writeln!(
+        output,
+        "static const __{}_entry_t {}_mph_table[{}] = {{",
+        output_name, output_name, mph.slots
+    )?;
+
+    let len_suffix = if compress { "_compressed_len" } else { "_len" };
+
+    for slot in &mph.slot_to_key {
+        match slot {
+            Some(key_index) => {
+                let entry = &entries[*key_index];
+                writeln!(
+                    output,
+                    "{{ \"{}\", (const uint8_t*){}.{}, {}.{}{} }},",
+                    entry.key, output_name, entry.field_path, output_name, entry.field_path, len_suffix
+                )?;
+            }
+            None => {
+                writeln!(output, "{{ NULL, NULL, 0 }},")?;
+            }
+        }
+    }
+
+    writeln!(output, "}};")?;
+
+    writeln!(
+        output,
+        "const uint8_t* {}_get(const char* path, size_t* out_len) {{",
+        output_name
+    )?;
+    writeln!(output, "size_t path_len = strlen(path);")?;
+    writeln!(
+        output,
+        "uint32_t bucket = finch_fnv1a(0, path, path_len) % {}u;",
+        mph.buckets
+    )?;
+    writeln!(output, "uint32_t d = {}_mph_displacements[bucket];", output_name)?;
+    writeln!(
+        output,
+        "uint32_t slot = finch_fnv1a(d, path, path_len) % {}u;",
+        mph.slots
+    )?;
+    writeln!(
+        output,
+        "const __{}_entry_t *entry = &{}_mph_table[slot];",
+        output_name, output_name
+    )?;
+    writeln!(
+        output,
+        "if (entry->path == NULL || strcmp(entry->path, path) != 0) return NULL;"
+    )?;
+    writeln!(output, "if (out_len) *out_len = entry->len;")?;
+    writeln!(output, "return entry->data;")?;
+    writeln!(output, "}}")?;
+
+    Ok(())
+}
+
+/// FNV-1a, seeded, shared by the minimal perfect hash build (Rust side) and the generated
+/// runtime lookup (C side) — both must hash identically for the displacement table to hold.
+const FNV1A_HELPER: &str = r#"
+static uint32_t finch_fnv1a(uint32_t seed, const char *data, size_t len) {
+    uint32_t hash = 0x811c9dc5u ^ seed;
+    for (size_t i = 0; i < len; i++) {
+        hash ^= (uint8_t)data[i];
+        hash *= 0x01000193u;
+    }
+    hash ^= hash >> 16;
+    hash *= 0x85ebca6bu;
+    hash ^= hash >> 13;
+    hash *= 0xc2b2ae35u;
+    hash ^= hash >> 16;
+    return hash;
+}
+"#;
+
+fn struct_fieldify_impl(
+    path: &Path,
+    output: &mut impl Write,
+    flags: &GenFlags,
+    config: &Config,
+) -> Result<(), FinchError> {
+    if path.is_dir() {
+        writeln!(output, "{{")?;
+
+        for entry in path.read_dir()? {
+            let entry = entry?;
+            let path = entry.path();
+
+            struct_fieldify_impl(&path, output, flags, config)?;
+        }
+
+        writeln!(output, "}},")?;
+    } else if flags.compress {
+        let contents = std::fs::read(path)?;
+        let decompressed_len = contents.len();
+        let compressed = deflate(&contents);
+        let compressed_len = compressed.len();
+
+        write_byte_array(output, &compressed)?;
+
+        writeln!(output, "{},", decompressed_len)?;
+        writeln!(output, "{},", compressed_len)?;
+    } else {
+        let output_type = config.classify(path);
+
+        match output_type {
+            AssetOutputType::String => {
+                let contents = std::fs::read_to_string(path)?;
+                let contents = if flags.minify {
+                    crate::minify::minify(path, &contents)
+                } else {
+                    contents
+                };
+                // Measured before C-escaping: `_len` is the runtime string length (matching
+                // `string_asset_len`, which sizes the header's array the same way), not the
+                // inflated length of the escaped C string literal below.
+                let contents_len = contents.len();
+
+                let contents = contents.replace('\n', "\\n");
+                let contents = contents.replace('\r', "\\r");
+                let contents = contents.replace('\t', "\\t");
+                let contents = contents.replace('\"', "\\\"");
+
+                writeln!(output, "\"{}\",", contents)?;
+                writeln!(output, "{},", contents_len)?;
+            }
+            AssetOutputType::Bytes => {
+                let contents = std::fs::read(path)?;
+                let contents_len = contents.len();
+
+                write_byte_array(output, &contents)?;
+
+                writeln!(output, "{},", contents_len)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_byte_array(output: &mut impl Write, bytes: &[u8]) -> Result<(), FinchError> {
+    writeln!(output, "{{")?;
+
+    const BYTES_PER_LINE: usize = 16;
+
+    let mut bytes_in_line = 0;
+
+    for byte in bytes {
+        write!(output, "0x{:02x}, ", byte)?;
+
+        bytes_in_line += 1;
+
+        if bytes_in_line == BYTES_PER_LINE {
+            writeln!(output)?;
+            bytes_in_line = 0;
+        }
+    }
+
+    if bytes_in_line != 0 {
+        writeln!(output)?;
+    }
+
+    writeln!(output, "}},")?;
+
+    Ok(())
+}
+
+/// Emits the `{name}_data()` lazy accessors: inflate-once into a static buffer sized from
+/// the asset's known (decompressed) file size, cached behind a `static int done` guard so
+/// repeated calls are free. `field_prefix` is the dotted struct-field path leading here
+/// (e.g. `"shaders."`), also used (with dots replaced by underscores) to keep the buffer,
+/// guard and function name globally unique across nested directories.
+fn struct_fieldify_impl_accessors(
+    path: &Path,
+    output: &mut impl Write,
+    output_name: &str,
+    field_prefix: &str,
+    config: &Config,
+) -> Result<(), FinchError> {
+    let name = path.file_stem().unwrap().to_str().unwrap();
+    let name = name.replace('-', "_");
+
+    if path.is_dir() {
+        let prefix = format!("{}{}.", field_prefix, name);
+
+        for entry in path.read_dir()? {
+            let entry = entry?;
+            let path = entry.path();
+
+            struct_fieldify_impl_accessors(&path, output, output_name, &prefix, config)?;
+        }
+    } else {
+        let field_path = format!("{}{}", field_prefix, name);
+        let qualified = field_path.replace('.', "_");
+
+        let output_type = config.classify(path);
+        let decompressed_len = path.metadata()?.len();
+        let buf_len = match output_type {
+            AssetOutputType::String => decompressed_len + 1,
+            AssetOutputType::Bytes => decompressed_len,
+        };
+
+        writeln!(
+            output,
+            "static uint8_t {}_buf[{}]{};",
+            qualified,
+            buf_len,
+            config.align_attribute(path)
+        )?;
+        writeln!(output, "static int {}_done = 0;", qualified)?;
+        writeln!(output, "const uint8_t* {}_data(void) {{", qualified)?;
+        writeln!(output, "if (!{}_done) {{", qualified)?;
+        writeln!(
+            output,
+            "finch_inflate({}_buf, {}.{}_len, {}.{}, {}.{}_compressed_len);",
+            qualified, output_name, field_path, output_name, field_path, output_name, field_path
+        )?;
+        writeln!(output, "{}_done = 1;", qualified)?;
+        writeln!(output, "}}")?;
+        writeln!(output, "return {}_buf;", qualified)?;
+        writeln!(output, "}}")?;
+    }
+
+    Ok(())
+}
+
+/// A minimal, public-domain-style DEFLATE decoder, adapted from Mark Adler's "puff"
+/// reference implementation. Inflates a complete raw DEFLATE stream (stored, fixed-Huffman
+/// and dynamic-Huffman blocks) into a caller-provided buffer of known size.
+const INFLATE_DECODER: &str = r#"
+struct finch_inflate_state {
+    const uint8_t *in;
+    size_t in_len;
+    size_t in_pos;
+    uint32_t bit_buf;
+    int bit_cnt;
+    uint8_t *out;
+    size_t out_len;
+    size_t out_pos;
+};
+
+static int finch_inflate_bits(struct finch_inflate_state *s, int need) {
+    long val = s->bit_buf;
+    while (s->bit_cnt < need) {
+        if (s->in_pos == s->in_len) return -1;
+        val |= (long)(s->in[s->in_pos++]) << s->bit_cnt;
+        s->bit_cnt += 8;
+    }
+    s->bit_buf = (uint32_t)(val >> need);
+    s->bit_cnt -= need;
+    return (int)(val & ((1L << need) - 1));
+}
+
+struct finch_huffman {
+    short counts[16];
+    short symbols[288];
+};
+
+static int finch_huffman_decode(struct finch_inflate_state *s, const struct finch_huffman *h) {
+    int code = 0, first = 0, index = 0;
+    for (int len = 1; len <= 15; len++) {
+        int bit = finch_inflate_bits(s, 1);
+        if (bit < 0) return -1;
+        code |= bit;
+        int count = h->counts[len];
+        if (code - first < count) return h->symbols[index + (code - first)];
+        index += count;
+        first += count;
+        first <<= 1;
+        code <<= 1;
+    }
+    return -1;
+}
+
+static void finch_huffman_build(struct finch_huffman *h, const short *lengths, int n) {
+    for (int len = 0; len <= 15; len++) h->counts[len] = 0;
+    for (int i = 0; i < n; i++) h->counts[lengths[i]]++;
+    h->counts[0] = 0;
+
+    short offsets[16];
+    offsets[1] = 0;
+    for (int len = 1; len < 15; len++) offsets[len + 1] = offsets[len] + h->counts[len];
+
+    for (int i = 0; i < n; i++) {
+        if (lengths[i] != 0) h->symbols[offsets[lengths[i]]++] = (short)i;
+    }
+}
+
+static int finch_inflate_stored(struct finch_inflate_state *s) {
+    s->bit_buf = 0;
+    s->bit_cnt = 0;
+    if (s->in_pos + 4 > s->in_len) return -1;
+    unsigned len = s->in[s->in_pos] | (s->in[s->in_pos + 1] << 8);
+    s->in_pos += 4;
+    if (s->out_pos + len > s->out_len || s->in_pos + len > s->in_len) return -1;
+    for (unsigned i = 0; i < len; i++) s->out[s->out_pos++] = s->in[s->in_pos++];
+    return 0;
+}
+
+static const short FINCH_LEN_BASE[29] = {
+    3,4,5,6,7,8,9,10,11,13,15,17,19,23,27,31,35,43,51,59,67,83,99,115,131,163,195,227,258
+};
+static const short FINCH_LEN_EXTRA[29] = {
+    0,0,0,0,0,0,0,0,1,1,1,1,2,2,2,2,3,3,3,3,4,4,4,4,5,5,5,5,0
+};
+static const short FINCH_DIST_BASE[30] = {
+    1,2,3,4,5,7,9,13,17,25,33,49,65,97,129,193,257,385,513,769,
+    1025,1537,2049,3073,4097,6145,8193,12289,16385,24577
+};
+static const short FINCH_DIST_EXTRA[30] = {
+    0,0,0,0,1,1,2,2,3,3,4,4,5,5,6,6,7,7,8,8,9,9,10,10,11,11,12,12,13,13
+};
+
+static int finch_inflate_block(struct finch_inflate_state *s, const struct finch_huffman *lencode,
+                                const struct finch_huffman *distcode) {
+    for (;;) {
+        int symbol = finch_huffman_decode(s, lencode);
+        if (symbol < 0) return -1;
+        if (symbol < 256) {
+            if (s->out_pos == s->out_len) return -1;
+            s->out[s->out_pos++] = (uint8_t)symbol;
+        } else if (symbol == 256) {
+            return 0;
+        } else {
+            symbol -= 257;
+            if (symbol >= 29) return -1;
+            int len = FINCH_LEN_BASE[symbol] + finch_inflate_bits(s, FINCH_LEN_EXTRA[symbol]);
+
+            int dist_symbol = finch_huffman_decode(s, distcode);
+            if (dist_symbol < 0 || dist_symbol >= 30) return -1;
+            int dist = FINCH_DIST_BASE[dist_symbol] + finch_inflate_bits(s, FINCH_DIST_EXTRA[dist_symbol]);
+
+            if ((size_t)dist > s->out_pos || s->out_pos + (size_t)len > s->out_len) return -1;
+            for (int i = 0; i < len; i++) {
+                s->out[s->out_pos] = s->out[s->out_pos - dist];
+                s->out_pos++;
+            }
+        }
+    }
+}
+
+static int finch_inflate_fixed(struct finch_inflate_state *s) {
+    static struct finch_huffman lencode, distcode;
+    static int built = 0;
+    if (!built) {
+        short lengths[288];
+        int i = 0;
+        for (; i < 144; i++) lengths[i] = 8;
+        for (; i < 256; i++) lengths[i] = 9;
+        for (; i < 280; i++) lengths[i] = 7;
+        for (; i < 288; i++) lengths[i] = 8;
+        finch_huffman_build(&lencode, lengths, 288);
+
+        short dlengths[30];
+        for (i = 0; i < 30; i++) dlengths[i] = 5;
+        finch_huffman_build(&distcode, dlengths, 30);
+        built = 1;
+    }
+    return finch_inflate_block(s, &lencode, &distcode);
+}
+
+static const short FINCH_CLEN_ORDER[19] = {
+    16,17,18,0,8,7,9,6,10,5,11,4,12,3,13,2,14,1,15
+};
+
+static int finch_inflate_dynamic(struct finch_inflate_state *s) {
+    int hlit = finch_inflate_bits(s, 5) + 257;
+    int hdist = finch_inflate_bits(s, 5) + 1;
+    int hclen = finch_inflate_bits(s, 4) + 4;
+
+    short clen_lengths[19] = {0};
+    for (int i = 0; i < hclen; i++) {
+        int v = finch_inflate_bits(s, 3);
+        if (v < 0) return -1;
+        clen_lengths[FINCH_CLEN_ORDER[i]] = (short)v;
+    }
+
+    struct finch_huffman clencode;
+    finch_huffman_build(&clencode, clen_lengths, 19);
+
+    short lengths[288 + 30] = {0};
+    int n = 0;
+    while (n < hlit + hdist) {
+        int symbol = finch_huffman_decode(s, &clencode);
+        if (symbol < 0) return -1;
+        if (symbol < 16) {
+            lengths[n++] = (short)symbol;
+        } else if (symbol == 16) {
+            if (n == 0) return -1;
+            int repeat = finch_inflate_bits(s, 2) + 3;
+            while (repeat-- > 0 && n < hlit + hdist) { lengths[n] = lengths[n - 1]; n++; }
+        } else if (symbol == 17) {
+            int repeat = finch_inflate_bits(s, 3) + 3;
+            while (repeat-- > 0 && n < hlit + hdist) lengths[n++] = 0;
+        } else {
+            int repeat = finch_inflate_bits(s, 7) + 11;
+            while (repeat-- > 0 && n < hlit + hdist) lengths[n++] = 0;
+        }
+    }
+
+    struct finch_huffman lencode, distcode;
+    finch_huffman_build(&lencode, lengths, hlit);
+    finch_huffman_build(&distcode, lengths + hlit, hdist);
+
+    return finch_inflate_block(s, &lencode, &distcode);
+}
+
+int finch_inflate(uint8_t *dest, size_t dest_len, const uint8_t *src, size_t src_len) {
+    struct finch_inflate_state s;
+    s.in = src;
+    s.in_len = src_len;
+    s.in_pos = 0;
+    s.bit_buf = 0;
+    s.bit_cnt = 0;
+    s.out = dest;
+    s.out_len = dest_len;
+    s.out_pos = 0;
+
+    int last;
+    do {
+        last = finch_inflate_bits(&s, 1);
+        if (last < 0) return -1;
+        int type = finch_inflate_bits(&s, 2);
+
+        int result;
+        switch (type) {
+            case 0: result = finch_inflate_stored(&s); break;
+            case 1: result = finch_inflate_fixed(&s); break;
+            case 2: result = finch_inflate_dynamic(&s); break;
+            default: return -1;
+        }
+        if (result != 0) return result;
+    } while (!last);
+
+    return 0;
+}
+"#;
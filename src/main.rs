@@ -1,9 +1,7 @@
-use std::{
-    io::Write,
-    path::{Path, PathBuf},
-};
+use std::{collections::HashMap, path::PathBuf};
 
 use clap::Parser;
+use finch::GenerateOptions;
 
 #[derive(Parser)]
 #[clap(
@@ -11,261 +9,92 @@ use clap::Parser;
     about = "A small CLI program to compile an asset directory into a C header file."
 )]
 struct Opt {
-    directory: PathBuf,
+    /// One or more asset directories to embed; generates a `{name}.h`/`.c` pair per directory.
+    #[clap(required = true)]
+    directories: Vec<PathBuf>,
+    /// Explicit output name, overriding the directory's file stem. Only valid with a single
+    /// directory.
+    #[clap(short, long)]
     output: Option<String>,
+    /// Directory to write the generated header/impl pair(s) into, created if missing.
+    /// Defaults to the current directory.
+    #[clap(short = 'd', long = "output-dir")]
+    output_dir: Option<PathBuf>,
     #[clap(short, long)]
     c_file: bool,
+    /// Store assets DEFLATE-compressed and generate an inflate accessor for each one.
+    #[clap(long)]
+    compress: bool,
+    /// Generate a `{name}_get(path, len)` runtime lookup over every asset's relative path.
+    #[clap(long)]
+    lookup: bool,
+    /// Minify `String` assets (stripping insignificant whitespace/comments for recognized
+    /// extensions) before embedding.
+    #[clap(long)]
+    minify: bool,
+    /// Read extension classification and alignment from this `finch.toml` instead of the
+    /// default `{directory}/finch.toml`.
+    #[clap(long)]
+    config: Option<PathBuf>,
 }
 
 fn main() {
     let opt = Opt::parse();
 
-    let directory = match opt.directory.canonicalize() {
-        Ok(path) => path,
-        Err(_) => {
-            eprintln!("Error: Invalid directory path.");
-            std::process::exit(1);
-        }
-    };
-
-    if !directory.is_dir() {
-        eprintln!("Error: Path is not a directory.");
+    if opt.output.is_some() && opt.directories.len() > 1 {
+        eprintln!("Error: an explicit output name can only be used with a single directory");
         std::process::exit(1);
     }
 
-    let output_name = match opt.output {
-        Some(path) => path,
-        None => directory.file_stem().unwrap().to_str().unwrap().to_string(),
-    };
-
-    let output_header = format!("{}.h", output_name);
-
-    let mut output = match std::fs::File::create(output_header) {
-        Ok(file) => file,
-        Err(_) => {
-            eprintln!("Error: Invalid output path.");
-            std::process::exit(1);
+    if opt.output.is_none() {
+        let mut by_output_name: HashMap<String, Vec<&PathBuf>> = HashMap::new();
+        for directory in &opt.directories {
+            let output_name = directory
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .to_string();
+            by_output_name.entry(output_name).or_default().push(directory);
         }
-    };
-
-    generate_header(&directory, &output_name, &mut output);
-
-    if opt.c_file {
-        let output_impl = format!("{}.c", output_name);
 
-        output = match std::fs::File::create(output_impl) {
-            Ok(file) => file,
-            Err(_) => {
-                eprintln!("Error: Invalid output path.");
+        for (output_name, directories) in &by_output_name {
+            if directories.len() > 1 {
+                eprintln!(
+                    "Error: directories {} all resolve to the output name \"{}\"; pass --output to disambiguate",
+                    directories
+                        .iter()
+                        .map(|d| d.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    output_name
+                );
                 std::process::exit(1);
             }
-        };
-    }
-
-    generate_impl(&directory, &output_name, &mut output, !opt.c_file);
-}
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-enum AssetOutputType {
-    String,
-    Bytes,
-}
-
-impl AssetOutputType {
-    pub fn guess_from_filepath(path: &Path) -> Self {
-        let extension = path.extension();
-
-        if let Some(extension) = extension {
-            if let Some(extension) = extension.to_str() {
-                match extension {
-                    "txt" | "json" | "xml" | "csv" | "html" | "htm" | "css" | "js" | "md"
-                    | "toml" | "rs" | "glsl" | "frag" | "vert" => Self::String,
-                    _ => Self::Bytes,
-                }
-            } else {
-                Self::Bytes
-            }
-        } else {
-            Self::Bytes
         }
     }
-}
-
-fn generate_header(directory: &Path, output_name: &str, output: &mut impl Write) {
-    writeln!(output, "#ifndef {}_H", output_name.to_uppercase()).unwrap();
-    writeln!(output, "#define {}_H", output_name.to_uppercase()).unwrap();
-
-    writeln!(output, "#include <stdint.h>").unwrap();
-    writeln!(output, "#include <stddef.h>").unwrap();
-
-    writeln!(output, "#ifdef __cplusplus").unwrap();
-    writeln!(output, "extern \"C\" {{").unwrap();
-    writeln!(output, "#endif").unwrap();
-
-    writeln!(output, "typedef struct {{").unwrap();
-
-    for entry in directory.read_dir().unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-
-        struct_fieldify(&path, output);
-    }
-
-    writeln!(output, "}} __{}_t;", output_name).unwrap();
 
-    writeln!(
-        output,
-        "extern const __{}_t {};",
-        output_name, output_name
-    )
-    .unwrap();
+    for directory in opt.directories {
+        let mut opts = GenerateOptions::new(directory)
+            .c_file(opt.c_file)
+            .compress(opt.compress)
+            .lookup(opt.lookup)
+            .minify(opt.minify);
 
-    writeln!(output, "#ifdef __cplusplus").unwrap();
-    writeln!(output, "}}").unwrap();
-    writeln!(output, "#endif").unwrap();
-
-    writeln!(output, "#endif").unwrap();
-}
-
-fn struct_fieldify(path: &Path, output: &mut impl Write) {
-    let name = path.file_stem().unwrap().to_str().unwrap();
-    let name = name.replace('-', "_");
-
-    if path.is_dir() {
-        writeln!(output, "struct {{").unwrap();
-
-        for entry in path.read_dir().unwrap() {
-            let entry = entry.unwrap();
-            let path = entry.path();
-
-            struct_fieldify(&path, output);
+        if let Some(output) = &opt.output {
+            opts = opts.output_name(output.clone());
         }
 
-        writeln!(output, "}} {};", name).unwrap();
-    } else {
-        let output_type = AssetOutputType::guess_from_filepath(path);
-        let filesize = path.metadata().unwrap().len();
-
-        match output_type {
-            AssetOutputType::String => {
-                writeln!(output, "const char {}[{} + 1];", name, filesize).unwrap();
-                writeln!(output, "const size_t {}_len;", name).unwrap();
-            }
-            AssetOutputType::Bytes => {
-                writeln!(output, "const uint8_t {}[{}];", name, filesize).unwrap();
-                writeln!(output, "const size_t {}_len;", name).unwrap();
-            }
+        if let Some(output_dir) = &opt.output_dir {
+            opts = opts.output_dir(output_dir.clone());
         }
-    }
-}
-
-fn generate_impl(directory: &Path, output_name: &str, output: &mut impl Write, single_file: bool) {
-    if single_file {
-        writeln!(
-            output,
-            "#ifdef {}_IMPLEMENTATION",
-            output_name.to_uppercase()
-        )
-        .unwrap();
-    } else {
-        writeln!(output, "#include \"{}.h\"", output_name).unwrap();
-    }
-
-    writeln!(output, "#include <stddef.h>").unwrap();
-    writeln!(output, "#include <stdint.h>").unwrap();
-
-    writeln!(output, "#ifdef __cplusplus").unwrap();
-    writeln!(output, "extern \"C\" {{").unwrap();
-    writeln!(output, "#endif").unwrap();
-
-    writeln!(
-        output,
-        "const __{}_t {} = {{",
-        output_name, output_name
-    )
-    .unwrap();
-
-    for entry in directory.read_dir().unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
-
-        struct_fieldify_impl(&path, output);
-    }
-
-    writeln!(output, "}};").unwrap();
-
-    writeln!(output, "#ifdef __cplusplus").unwrap();
-    writeln!(output, "}}").unwrap();
-    writeln!(output, "#endif").unwrap();
-
-    if single_file {
-        writeln!(
-            output,
-            "#undef {}_IMPLEMENTATION",
-            output_name.to_uppercase()
-        )
-        .unwrap();
-
-        writeln!(output, "#endif").unwrap();
-    }
-}
 
-fn struct_fieldify_impl(path: &Path, output: &mut impl Write) {
-    if path.is_dir() {
-        writeln!(output, "{{").unwrap();
-
-        for entry in path.read_dir().unwrap() {
-            let entry = entry.unwrap();
-            let path = entry.path();
-
-            struct_fieldify_impl(&path, output);
+        if let Some(config) = &opt.config {
+            opts = opts.config(config.clone());
         }
 
-        writeln!(output, "}},").unwrap();
-    } else {
-        let output_type = AssetOutputType::guess_from_filepath(path);
-
-        match output_type {
-            AssetOutputType::String => {
-                let contents = std::fs::read_to_string(path).unwrap();
-                let contents = contents.replace('\n', "\\n");
-                let contents = contents.replace('\r', "\\r");
-                let contents = contents.replace('\t', "\\t");
-                let contents = contents.replace('\"', "\\\"");
-
-                let contents_len = contents.len();
-
-                writeln!(output, "\"{}\",", contents).unwrap();
-                writeln!(output, "{},", contents_len).unwrap();
-            }
-            AssetOutputType::Bytes => {
-                let contents = std::fs::read(path).unwrap();
-                let contents_len = contents.len();
-
-                writeln!(output, "{{").unwrap();
-
-                const BYTES_PER_LINE: usize = 16;
-
-                let mut bytes_in_line = 0;
-
-                for byte in contents {
-                    write!(output, "0x{:02x}, ", byte).unwrap();
-
-                    bytes_in_line += 1;
-
-                    if bytes_in_line == BYTES_PER_LINE {
-                        writeln!(output).unwrap();
-                        bytes_in_line = 0;
-                    }
-                }
-
-                if bytes_in_line != 0 {
-                    writeln!(output).unwrap();
-                }
-
-                writeln!(output, "}},").unwrap();
-                writeln!(output, "{},", contents_len).unwrap();
-            }
+        if let Err(err) = finch::generate_files(&opts) {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
         }
     }
 }
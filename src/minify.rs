@@ -0,0 +1,149 @@
+//! Minimal, dependency-free minifiers for text assets embedded with `--minify`. Dispatch is
+//! by file extension; unrecognized extensions are returned unchanged.
+
+use std::path::Path;
+
+/// Strips insignificant whitespace/comments from `contents` according to `path`'s
+/// extension.
+pub(crate) fn minify(path: &Path, contents: &str) -> String {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => minify_json(contents),
+        Some("css") => minify_css(contents),
+        Some("js") => minify_js(contents),
+        _ => contents.to_string(),
+    }
+}
+
+/// Strips `/* block */` comments (and, if `line_comments` is set, `// line` comments),
+/// leaving the contents of any single/double/backtick-quoted string untouched.
+fn strip_comments(contents: &str, line_comments: bool) -> String {
+    let mut output = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(quote) = in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == quote {
+                in_string = None;
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' | '`' => {
+                in_string = Some(c);
+                output.push(c);
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == '*' && chars.peek() == Some(&'/') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            '/' if line_comments && chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Collapses every run of whitespace to a single space and trims the ends, leaving the
+/// contents of any single/double/backtick-quoted string untouched. If `preserve_newlines` is
+/// set, a run containing a newline collapses to a single `\n` instead of a space, since JS
+/// newlines can be significant (automatic semicolon insertion).
+fn collapse_whitespace(contents: &str, preserve_newlines: bool) -> String {
+    let mut output = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string: Option<char> = None;
+    let mut pending_whitespace: Option<bool> = None;
+
+    let flush = |output: &mut String, had_newline: bool| {
+        if !output.is_empty() {
+            output.push(if preserve_newlines && had_newline { '\n' } else { ' ' });
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        if let Some(quote) = in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == quote {
+                in_string = None;
+            }
+            continue;
+        }
+
+        if c == '"' || c == '\'' || c == '`' {
+            if let Some(had_newline) = pending_whitespace.take() {
+                flush(&mut output, had_newline);
+            }
+            in_string = Some(c);
+            output.push(c);
+        } else if c.is_whitespace() {
+            let had_newline = pending_whitespace.unwrap_or(false) || c == '\n';
+            pending_whitespace = Some(had_newline);
+        } else {
+            if let Some(had_newline) = pending_whitespace.take() {
+                flush(&mut output, had_newline);
+            }
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+fn minify_css(contents: &str) -> String {
+    collapse_whitespace(&strip_comments(contents, false), false)
+}
+
+fn minify_js(contents: &str) -> String {
+    collapse_whitespace(&strip_comments(contents, true), true)
+}
+
+/// JSON has no comments, and (unlike CSS/JS) whitespace inside strings must be preserved
+/// exactly, so this only strips whitespace that falls outside a string literal.
+fn minify_json(contents: &str) -> String {
+    let mut output = String::with_capacity(contents.len());
+    let mut chars = contents.chars();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    output.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            output.push(c);
+        } else if !c.is_whitespace() {
+            output.push(c);
+        }
+    }
+
+    output
+}